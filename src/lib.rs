@@ -1,8 +1,21 @@
 //! # Fast Boolean Algebraic Normal Form (ANF) Transformation functions
-//! This crate provides two functions to transform cellular automata truth tables expressed as unsigned integers or boolean arrays into their Algebraic Normal Form (ANF) representation.
+//! This crate provides functions to transform cellular automata truth tables expressed as unsigned integers, boolean arrays or packed `u64` limbs into their Algebraic Normal Form (ANF) representation.
 
 use std::mem::size_of;
-use num_traits::{AsPrimitive, NumCast, Unsigned};
+use num_traits::{AsPrimitive, NumCast, PrimInt, Unsigned};
+
+/// Precomputed shift-and-mask constants used by [`fast_bool_anf_transform_word_array`] for within-limb
+/// butterfly steps, one per power-of-two `blocksize` strictly smaller than 64 (1, 2, 4, 8, 16, 32).
+/// `LOW_MASK[i]` selects the source bit of every pair spaced `2^i` bits apart, so that
+/// `word ^= (word & LOW_MASK[i]) << (1 << i)` performs all 32 pairwise XORs of that step at once.
+const LOW_MASK: [u64; 6] = [
+    0x5555_5555_5555_5555,
+    0x3333_3333_3333_3333,
+    0x0F0F_0F0F_0F0F_0F0F,
+    0x00FF_00FF_00FF_00FF,
+    0x0000_FFFF_0000_FFFF,
+    0x0000_0000_FFFF_FFFF,
+];
 
 /// Fast ANF transformation for cellular automata truth table rules expressed as unsigned integers
 /// # Arguments
@@ -72,6 +85,98 @@ pub fn fast_bool_anf_transform_unsigned<
     final_f
 }
 
+/// Error returned by [`fast_bool_anf_transform_checked`] when the requested transform cannot be
+/// performed safely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnfError {
+    /// The unsigned type is not large enough to hold a truth table of `2^n` entries
+    TypeTooNarrow,
+    /// The rule number is greater than or equal to `2^(2^n)`, n being the number of variables in the function
+    RuleOutOfRange,
+}
+
+impl std::fmt::Display for AnfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnfError::TypeTooNarrow => write!(
+                f,
+                "unsigned type is not large enough to hold the rule number, please use a larger unsigned type"
+            ),
+            AnfError::RuleOutOfRange => write!(
+                f,
+                "the rule number must be less than 2^(2^n), n being the number of variables in the function"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AnfError {}
+
+/// Fast ANF transformation for cellular automata truth table rules expressed as unsigned integers,
+/// validating its inputs in all builds instead of only under `debug_assertions`
+///
+/// [`fast_bool_anf_transform_unsigned`]'s width and range checks only fire in debug builds, so a
+/// release build silently produces garbage when the type is too small or `rule_number` is out of
+/// range. This variant tightens the trait bound to [`PrimInt`], takes the type's bit width from
+/// `size_of` and uses [`PrimInt`]'s shifts to validate both conditions unconditionally, returning a [`AnfError`] instead of
+/// panicking, which makes it safe to call on untrusted input.
+/// # Arguments
+/// * `rule_number` - The rule number truth table to transform
+/// * `num_variables_function` - The number of variables in the cellular automata function
+/// # Returns
+/// The ANF transformed rule number, or an [`AnfError`] if the type is too narrow or the rule number is out of range
+/// # Example
+/// ```
+/// use fast_boolean_anf_transform::{fast_bool_anf_transform_checked, AnfError};
+/// assert_eq!(fast_bool_anf_transform_checked(3u32, 2), Ok(5)); // rule 3: 1 ^ x1
+/// assert_eq!(fast_bool_anf_transform_checked(16u32, 2), Err(AnfError::RuleOutOfRange));
+/// assert_eq!(fast_bool_anf_transform_checked(16u16, 5), Err(AnfError::TypeTooNarrow));
+/// ```
+pub fn fast_bool_anf_transform_checked<U: PrimInt>(
+    rule_number: U,
+    num_variables_function: usize,
+) -> Result<U, AnfError> {
+    let type_bits = size_of::<U>() << 3;
+    let domain_size = 1usize
+        .checked_shl(num_variables_function as u32)
+        .unwrap_or(usize::MAX);
+
+    if type_bits < domain_size {
+        return Err(AnfError::TypeTooNarrow);
+    }
+
+    if rule_number < U::zero() {
+        return Err(AnfError::RuleOutOfRange);
+    }
+
+    if domain_size < type_bits && rule_number >= (U::one() << domain_size) {
+        return Err(AnfError::RuleOutOfRange);
+    }
+
+    let u0 = U::zero();
+    let u1 = U::one();
+    let mut blocksize = 1usize;
+    let mut final_f = rule_number;
+    for _ in 0..num_variables_function {
+        let mut source = 0usize;
+        while source < domain_size {
+            let target = source + blocksize;
+            for i in 0..blocksize {
+                let f_target_i = ((final_f >> (target + i)) & u1) != u0;
+                let f_source_i = ((final_f >> (source + i)) & u1) != u0;
+                if f_target_i ^ f_source_i {
+                    final_f = final_f | (u1 << (target + i));
+                } else {
+                    final_f = final_f & !(u1 << (target + i));
+                }
+            }
+            source += blocksize << 1;
+        }
+        blocksize <<= 1;
+    }
+    Ok(final_f)
+}
+
 /// Fast ANF transformation for cellular automata truth table rules expressed as boolean arrays
 /// # Arguments
 /// * `rule_truth_table` - The rule truth table to transform, first element is the output for input 0, second element is the output for input 1, etc.
@@ -107,9 +212,254 @@ pub fn fast_bool_anf_transform_bool_array(rule_truth_table: &mut [bool]) {
     }
 }
 
+/// Fast ANF transformation for cellular automata truth table rules packed into `u64` limbs
+///
+/// Bit `k` of limb `j` holds the truth table output for input `64 * j + k`. Packing the table this
+/// way uses 8x less memory than [`fast_bool_anf_transform_bool_array`] and lets the butterfly run a
+/// whole limb (or more) at a time instead of one bit at a time, which is a large speedup for `n >= 10`.
+/// # Arguments
+/// * `word_array` - The rule truth table, packed bitwise into `u64` limbs, little-endian within each limb
+/// * `num_variables_function` - The number of variables in the cellular automata function
+/// # Panics
+/// Panics if `word_array` does not hold exactly `ceil(2^n / 64)` limbs, n being the number of variables in the function (debug only)
+/// # Example
+/// ```
+/// use fast_boolean_anf_transform::fast_bool_anf_transform_word_array;
+/// let mut word_array = [0b0010u64]; // rule 2: x0 . !x1 (bit 1 set)
+/// fast_bool_anf_transform_word_array(&mut word_array, 2);
+/// assert_eq!(word_array, [0b1010]); // rule 2: x0 ^ (x0 . x1)
+/// ```
+pub fn fast_bool_anf_transform_word_array(word_array: &mut [u64], num_variables_function: usize) {
+    #[cfg(debug_assertions)]
+    if word_array.len() != (1usize << num_variables_function).div_ceil(64) {
+        panic!("The word array must hold exactly ceil(2^n / 64) limbs, n being the number of variables in the function");
+    }
+
+    let mut blocksize = 1usize;
+    for _ in 0..num_variables_function {
+        if blocksize >= 64 {
+            let words_per_block = blocksize / 64;
+            let mut source = 0;
+            while source < word_array.len() {
+                for i in 0..words_per_block {
+                    word_array[source + words_per_block + i] ^= word_array[source + i];
+                }
+                source += words_per_block << 1;
+            }
+        } else {
+            let mask = LOW_MASK[blocksize.trailing_zeros() as usize];
+            for limb in word_array.iter_mut() {
+                *limb ^= (*limb & mask) << blocksize;
+            }
+        }
+        blocksize <<= 1;
+    }
+}
+
+/// Fast ANF transformation for cellular automata truth table rules expressed as arbitrary-precision
+/// rule numbers
+///
+/// [`fast_bool_anf_transform_unsigned`] is capped at whatever fixed-width unsigned integer the caller
+/// picks, which makes functions of more than 7 variables impossible with `u128`. This function lifts
+/// that ceiling by treating `rule` as a little-endian bit vector of length `2^n` spread across
+/// `ceil(2^n / 64)` limbs, the same packed representation used by [`fast_bool_anf_transform_word_array`],
+/// which it reuses directly.
+/// # Arguments
+/// * `rule` - The rule number to transform, as a little-endian bit vector packed into `u64` limbs
+/// * `num_variables` - The number of variables in the cellular automata function
+/// # Panics
+/// Panics if `rule` does not hold exactly `ceil(2^n / 64)` limbs, n being the number of variables in the function (debug only)
+/// # Example
+/// ```
+/// use fast_boolean_anf_transform::fast_bool_anf_transform_limbs;
+/// let mut rule = [0b0010u64]; // rule 2: x0 . !x1 (bit 1 set)
+/// fast_bool_anf_transform_limbs(&mut rule, 2);
+/// assert_eq!(rule, [0b1010]); // rule 2: x0 ^ (x0 . x1)
+/// ```
+pub fn fast_bool_anf_transform_limbs(rule: &mut [u64], num_variables: usize) {
+    fast_bool_anf_transform_word_array(rule, num_variables)
+}
+
+/// Walsh-Hadamard transform of a Boolean function's truth table
+///
+/// The output is expressed in `+1`/`-1` polarity (`false` maps to `+1`, `true` maps to `-1`), and the
+/// transform reuses the same doubling-butterfly structure as the ANF transforms above, but combining
+/// pairs with `a' = a + b`, `b' = a - b` instead of XOR. The resulting Walsh spectrum is the basis for
+/// the cryptographic metrics below.
+/// # Arguments
+/// * `truth_table` - The rule truth table, first element is the output for input 0, second element is the output for input 1, etc.
+/// # Returns
+/// The Walsh spectrum of the truth table
+/// # Panics
+/// Panics if the truth table length is not equal to 2^n, n being the number of variables in the function (debug only)
+/// # Example
+/// ```
+/// use fast_boolean_anf_transform::fast_walsh_hadamard_transform;
+/// assert_eq!(fast_walsh_hadamard_transform(&[false, true, true, false]), vec![0, 0, 0, 4]); // x0 ^ x1
+/// ```
+pub fn fast_walsh_hadamard_transform(truth_table: &[bool]) -> Vec<i64> {
+    let input_size = truth_table.len().trailing_zeros() as usize;
+
+    #[cfg(debug_assertions)]
+    if truth_table.len() != 1 << input_size {
+        panic!("The input truth table must have a size of 2^n, n being the number of variables in the function");
+    }
+
+    let mut w: Vec<i64> = truth_table.iter().map(|&b| if b { -1 } else { 1 }).collect();
+
+    let mut blocksize = 1;
+    while blocksize < (1 << input_size) {
+        let mut source = 0;
+        while source < (1 << input_size) {
+            for i in 0..blocksize {
+                let a = source + i;
+                let b = a + blocksize;
+                let wa = w[a];
+                let wb = w[b];
+                w[a] = wa + wb;
+                w[b] = wa - wb;
+            }
+            source += blocksize << 1;
+        }
+        blocksize <<= 1;
+    }
+    w
+}
+
+/// Whether a Boolean function is balanced, i.e. takes the value `true` on exactly half of its inputs
+/// # Arguments
+/// * `truth_table` - The rule truth table, first element is the output for input 0, second element is the output for input 1, etc.
+/// # Returns
+/// `true` if the function is balanced
+/// # Example
+/// ```
+/// use fast_boolean_anf_transform::is_balanced;
+/// assert!(is_balanced(&[false, true, true, false])); // x0 ^ x1
+/// assert!(!is_balanced(&[false, true, false, false])); // x0 . !x1
+/// ```
+pub fn is_balanced(truth_table: &[bool]) -> bool {
+    fast_walsh_hadamard_transform(truth_table)[0] == 0
+}
+
+/// Nonlinearity of a Boolean function, i.e. the Hamming distance to the closest affine function
+/// # Arguments
+/// * `truth_table` - The rule truth table, first element is the output for input 0, second element is the output for input 1, etc.
+/// # Returns
+/// The nonlinearity of the function
+/// # Example
+/// ```
+/// use fast_boolean_anf_transform::nonlinearity;
+/// assert_eq!(nonlinearity(&[false, true, true, false]), 0); // x0 ^ x1 is affine
+/// assert_eq!(nonlinearity(&[false, true, false, false]), 1); // x0 . !x1
+/// ```
+pub fn nonlinearity(truth_table: &[bool]) -> usize {
+    let input_size = truth_table.len().trailing_zeros() as usize;
+    if input_size == 0 {
+        // constant function (0 variables): no affine function is any closer, nonlinearity is 0
+        return 0;
+    }
+
+    let max_abs_walsh_value = fast_walsh_hadamard_transform(truth_table)
+        .into_iter()
+        .map(|w| w.unsigned_abs())
+        .max()
+        .unwrap_or(0);
+    (1usize << (input_size - 1)) - (max_abs_walsh_value / 2) as usize
+}
+
+/// Algebraic degree of a Boolean function, computed from its ANF
+///
+/// The degree is the largest number of variables appearing in any monomial of the ANF, i.e. the
+/// maximum popcount of an index whose ANF coefficient bit is set. A constant function (ANF all
+/// zero, or only the constant term set) has degree 0.
+/// # Arguments
+/// * `anf_truth_table` - The ANF of a rule truth table, as produced by [`fast_bool_anf_transform_bool_array`]
+/// # Returns
+/// The algebraic degree of the function
+/// # Example
+/// ```
+/// use fast_boolean_anf_transform::algebraic_degree;
+/// assert_eq!(algebraic_degree(&[false, true, true, true]), 2); // x0 ^ x1 ^ (x0 . x1) has degree 2
+/// ```
+pub fn algebraic_degree(anf_truth_table: &[bool]) -> usize {
+    anf_truth_table
+        .iter()
+        .enumerate()
+        .filter(|(_, &bit)| bit)
+        .map(|(i, _)| i.count_ones() as usize)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Fast ANF transformation for vectorial Boolean functions (S-boxes) expressed as unsigned integers
+///
+/// A vectorial Boolean function with `n` inputs and `m` outputs is just `m` coordinate functions
+/// sharing the same input, so `lut[x]` is decomposed bit by bit: coordinate `j`'s truth table has
+/// bit `x` equal to bit `j` of `lut[x]`. Each coordinate truth table is then transformed with
+/// [`fast_bool_anf_transform_unsigned`], and the per-coordinate ANF rule numbers are returned in the
+/// same order, one `U` per output bit position.
+/// # Arguments
+/// * `lut` - The S-box lookup table, `lut[x]` is the output word for input `x`
+/// * `num_variables` - The number of input variables `n` of the S-box
+/// # Returns
+/// The per-coordinate ANF rule numbers, coordinate `j` at index `j`
+/// # Panics
+/// Panics if `lut` does not have a length of `2^n`, n being the number of variables in the function (debug only)
+/// Panics if the unsigned type is not large enough to hold a coordinate rule number (debug only)
+/// # Example
+/// ```
+/// use fast_boolean_anf_transform::fast_bool_anf_transform_sbox;
+/// // 2-bit S-box: lut[x] = x rotated left by 1 bit
+/// let lut = [0b00u8, 0b10, 0b01, 0b11];
+/// assert_eq!(fast_bool_anf_transform_sbox(&lut, 2), vec![4, 2, 0, 0, 0, 0, 0, 0]);
+/// ```
+pub fn fast_bool_anf_transform_sbox<
+    U: Unsigned
+        + std::ops::Shr<U, Output = U>
+        + std::ops::Shl<U, Output = U>
+        + std::ops::BitOr<U, Output = U>
+        + std::ops::BitAnd<U, Output = U>
+        + PartialOrd<U>
+        + std::ops::Not<Output = U>
+        + NumCast
+        + AsPrimitive<usize>
+        + Copy,
+>(
+    lut: &[U],
+    num_variables: usize,
+) -> Vec<U> {
+    #[cfg(debug_assertions)]
+    if lut.len() != 1 << num_variables {
+        panic!("The lookup table must have a length of 2^n, n being the number of variables in the function");
+    }
+
+    let u0: U = U::from(0).unwrap();
+    let u1: U = U::from(1).unwrap();
+    let num_coordinates = size_of::<U>() << 3;
+
+    (0..num_coordinates)
+        .map(|j| {
+            let uj: U = U::from(j).unwrap();
+            let mut coordinate_rule = u0;
+            for (x, &value) in lut.iter().enumerate() {
+                let ux: U = U::from(x).unwrap();
+                if (value >> uj) & u1 != u0 {
+                    coordinate_rule = coordinate_rule | (u1 << ux);
+                }
+            }
+            fast_bool_anf_transform_unsigned(coordinate_rule, num_variables)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{fast_bool_anf_transform_bool_array, fast_bool_anf_transform_unsigned};
+    use super::{
+        algebraic_degree, fast_bool_anf_transform_bool_array, fast_bool_anf_transform_checked,
+        fast_bool_anf_transform_limbs, fast_bool_anf_transform_sbox,
+        fast_bool_anf_transform_unsigned, fast_bool_anf_transform_word_array,
+        fast_walsh_hadamard_transform, is_balanced, nonlinearity, AnfError,
+    };
     #[test]
     fn test_fast_bool_anf_transform_unsigned() {
         assert_eq!(fast_bool_anf_transform_unsigned(0u32, 2), 0);
@@ -226,4 +576,145 @@ mod tests {
         let mut rule_truth_table = [false, false, false, false, true, true, true];
         fast_bool_anf_transform_bool_array(&mut rule_truth_table);
     }
+
+    #[test]
+    fn test_fast_bool_anf_transform_word_array() {
+        // single limb, n = 3, matches the bool-array rule 240 / 30 cases above
+        let mut word_array = [0b1111_0000u64];
+        fast_bool_anf_transform_word_array(&mut word_array, 3);
+        assert_eq!(word_array, [0b0001_0000]);
+
+        let mut word_array = [0b0001_1110u64];
+        fast_bool_anf_transform_word_array(&mut word_array, 3);
+        assert_eq!(word_array, [0b0001_1110]);
+
+        // two limbs, n = 7: exercises the whole-limb XOR path for blocksize >= 64
+        let mut word_array = [0x22u64, 0x1000000000u64];
+        fast_bool_anf_transform_word_array(&mut word_array, 7);
+        assert_eq!(word_array, [0x0a0a0a0a0a0a0a0au64, 0xfafafafa0a0a0a0au64]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fast_bool_anf_transform_word_array_not_enough_bits() {
+        let mut word_array = [0u64];
+        fast_bool_anf_transform_word_array(&mut word_array, 7);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fast_bool_anf_transform_word_array_too_many_bits() {
+        let mut word_array = [0u64; 10];
+        fast_bool_anf_transform_word_array(&mut word_array, 9);
+    }
+
+    #[test]
+    fn test_fast_bool_anf_transform_limbs() {
+        // n = 8, i.e. rule numbers up to 2^256: well past the u128 ceiling of fast_bool_anf_transform_unsigned
+        let mut rule = [0u64, 0, 0, 0];
+        rule[3] = 1u64 << 63; // bit 255 set: input 255 (all variables true) maps to true
+        fast_bool_anf_transform_limbs(&mut rule, 8);
+        assert_eq!(rule, [0, 0, 0, 1u64 << 63]);
+    }
+
+    #[test]
+    fn test_fast_walsh_hadamard_transform() {
+        assert_eq!(fast_walsh_hadamard_transform(&[false, false, false, false]), vec![4, 0, 0, 0]);
+        assert_eq!(fast_walsh_hadamard_transform(&[false, true, true, false]), vec![0, 0, 0, 4]); // x0 ^ x1
+        assert_eq!(fast_walsh_hadamard_transform(&[false, true, false, false]), vec![2, 2, -2, 2]); // x0 . !x1
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fast_walsh_hadamard_transform_wrong_input_size() {
+        let _ = fast_walsh_hadamard_transform(&[false, false, false]);
+    }
+
+    #[test]
+    fn test_is_balanced() {
+        assert!(is_balanced(&[false, true, true, false])); // x0 ^ x1
+        assert!(!is_balanced(&[false, true, false, false])); // x0 . !x1
+        assert!(!is_balanced(&[false, false, false, false])); // constant
+    }
+
+    #[test]
+    fn test_nonlinearity() {
+        assert_eq!(nonlinearity(&[false, true, true, false]), 0); // x0 ^ x1 is affine
+        assert_eq!(nonlinearity(&[false, true, false, false]), 1); // x0 . !x1
+        assert_eq!(nonlinearity(&[false, false, false, false]), 0); // constant
+        assert_eq!(nonlinearity(&[false]), 0); // 0-variable constant function
+        assert_eq!(nonlinearity(&[true]), 0); // 0-variable constant function
+    }
+
+    #[test]
+    fn test_algebraic_degree() {
+        assert_eq!(algebraic_degree(&[false, false, false, false]), 0); // constant 0
+        assert_eq!(algebraic_degree(&[true, false, false, false]), 0); // constant 1
+        assert_eq!(algebraic_degree(&[false, true, false, false]), 1); // x0
+        assert_eq!(algebraic_degree(&[false, true, true, true]), 2); // x0 ^ x1 ^ (x0 . x1)
+    }
+
+    #[test]
+    fn test_fast_bool_anf_transform_sbox() {
+        // 2-bit S-box: lut[x] = x rotated left by 1 bit
+        let lut = [0b00u8, 0b10, 0b01, 0b11];
+        assert_eq!(
+            fast_bool_anf_transform_sbox(&lut, 2),
+            vec![4, 2, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fast_bool_anf_transform_sbox_wrong_lut_length() {
+        let lut = [0u8, 2u8];
+        let _ = fast_bool_anf_transform_sbox(&lut, 2);
+    }
+
+    #[test]
+    fn test_fast_bool_anf_transform_checked() {
+        assert_eq!(fast_bool_anf_transform_checked(0u32, 2), Ok(0));
+        assert_eq!(fast_bool_anf_transform_checked(3u32, 2), Ok(5));
+        assert_eq!(fast_bool_anf_transform_checked(240u32, 3), Ok(16));
+        assert_eq!(fast_bool_anf_transform_checked(30u32, 3), Ok(30));
+    }
+
+    #[test]
+    fn test_fast_bool_anf_transform_checked_rule_number_too_large() {
+        assert_eq!(
+            fast_bool_anf_transform_checked(16u32, 2),
+            Err(AnfError::RuleOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_fast_bool_anf_transform_checked_not_enough_bits() {
+        assert_eq!(
+            fast_bool_anf_transform_checked(16u16, 5),
+            Err(AnfError::TypeTooNarrow)
+        );
+    }
+
+    #[test]
+    fn test_fast_bool_anf_transform_checked_type_exactly_fits() {
+        // 2^n == type width: every value of the type is a valid rule number, no range check needed
+        assert_eq!(fast_bool_anf_transform_checked(u32::MAX, 5), Ok(1));
+    }
+
+    #[test]
+    fn test_fast_bool_anf_transform_checked_num_variables_overflows_shift() {
+        // 2^n does not fit in a usize at all, let alone in the requested type
+        assert_eq!(
+            fast_bool_anf_transform_checked(5u32, usize::BITS as usize),
+            Err(AnfError::TypeTooNarrow)
+        );
+    }
+
+    #[test]
+    fn test_fast_bool_anf_transform_checked_negative_rule_number() {
+        assert_eq!(
+            fast_bool_anf_transform_checked(-1i32, 2),
+            Err(AnfError::RuleOutOfRange)
+        );
+    }
 }